@@ -1,46 +1,383 @@
 //! Concurrent Processing Module for EVA Rust Core
-//! 
+//!
 //! Provides high-performance concurrent processing capabilities
 
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::{RwLock, Semaphore};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, RwLock, Semaphore};
 use tokio::task::JoinHandle;
+use tokio_stream::wrappers::UnboundedReceiverStream;
 use rayon::prelude::*;
 use serde_json::Value;
 
+use crate::ProcessingResponse;
+
+/// Most wall-clock time charged to a request's vruntime for one quantum (one
+/// batch or pipeline stage). A batch or stage that runs longer is not cut
+/// short; it runs to completion and is still charged only this much.
+const SCHEDULER_QUANTUM: Duration = Duration::from_millis(10);
+
+/// Backoff between re-checks in [`FairScheduler::wait_for_turn`]'s poll loop.
+/// Short enough that a newly-admitted request starts promptly, long enough
+/// that a heap full of blocked requests doesn't spin, continually re-locking
+/// `self.heap`, while they wait their turn.
+const WAIT_FOR_TURN_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Per-request fairness bookkeeping maintained by the [`FairScheduler`]
+#[derive(Debug, Clone, Default)]
+pub struct FairnessStats {
+    pub vruntime: f64,
+    pub quanta_consumed: u64,
+}
+
+/// A request waiting on (or running in) the fair scheduler's min-heap
+#[derive(Debug, Clone)]
+struct ScheduledTask {
+    scheduler_key: String,
+    vruntime: f64,
+    weight: f64,
+}
+
+impl Eq for ScheduledTask {}
+
+impl PartialEq for ScheduledTask {
+    fn eq(&self, other: &Self) -> bool {
+        self.vruntime == other.vruntime
+    }
+}
+
+impl Ord for ScheduledTask {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // BinaryHeap is a max-heap; reverse so the smallest vruntime sorts first
+        other
+            .vruntime
+            .partial_cmp(&self.vruntime)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for ScheduledTask {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Cooperative, time-sliced scheduler that shares CPU fairly across in-flight requests
+///
+/// Modelled on CFS: every request accrues virtual runtime as it consumes
+/// quanta, and the heap always surfaces whichever request has run the
+/// least (weighted by its `priority`). This stops one large
+/// `concurrent_batch_process` call from starving smaller ones.
+#[derive(Debug, Clone)]
+struct FairScheduler {
+    heap: Arc<StdMutex<BinaryHeap<ScheduledTask>>>,
+    stats: Arc<StdMutex<HashMap<String, FairnessStats>>>,
+}
+
+impl FairScheduler {
+    fn new() -> Self {
+        FairScheduler {
+            heap: Arc::new(StdMutex::new(BinaryHeap::new())),
+            stats: Arc::new(StdMutex::new(HashMap::new())),
+        }
+    }
+
+    /// Derive a scheduler weight from the request's `priority` option.
+    /// Higher priority accrues vruntime more slowly, so it gets scheduled more often.
+    fn weight_from_priority(options: &HashMap<String, String>) -> f64 {
+        let priority: f64 = options
+            .get("priority")
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(1.0);
+        1.0 / priority.max(0.1)
+    }
+
+    fn register(&self, scheduler_key: &str, weight: f64) {
+        self.stats
+            .lock()
+            .unwrap()
+            .entry(scheduler_key.to_string())
+            .or_default();
+        self.heap.lock().unwrap().push(ScheduledTask {
+            scheduler_key: scheduler_key.to_string(),
+            vruntime: 0.0,
+            weight,
+        });
+    }
+
+    fn deregister(&self, scheduler_key: &str) {
+        let mut heap = self.heap.lock().unwrap();
+        let remaining: Vec<ScheduledTask> = heap
+            .drain()
+            .filter(|task| task.scheduler_key != scheduler_key)
+            .collect();
+        for task in remaining {
+            heap.push(task);
+        }
+    }
+
+    /// Block (cooperatively) until `scheduler_key` is among the `max_runners`
+    /// least-run entries in the heap.
+    ///
+    /// `max_runners` should track the task pool's permit count, so up to that
+    /// many requests are admitted to run concurrently -- just the ones that
+    /// have run the least -- rather than fully serializing all scheduled
+    /// traffic down to a single request at a time.
+    async fn wait_for_turn(&self, scheduler_key: &str, max_runners: usize) {
+        let max_runners = max_runners.max(1);
+        loop {
+            let can_run = {
+                let heap = self.heap.lock().unwrap();
+                let mut entries: Vec<&ScheduledTask> = heap.iter().collect();
+                entries.sort_by(|a, b| a.vruntime.partial_cmp(&b.vruntime).unwrap_or(Ordering::Equal));
+                let registered = entries.iter().any(|task| task.scheduler_key == scheduler_key);
+                !registered
+                    || entries
+                        .iter()
+                        .take(max_runners)
+                        .any(|task| task.scheduler_key == scheduler_key)
+            };
+            if can_run {
+                return;
+            }
+            tokio::time::sleep(WAIT_FOR_TURN_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Account one quantum's wall-clock time against `scheduler_key` (weighted by
+    /// its registered priority)
+    fn account_quantum(&self, scheduler_key: &str, elapsed: Duration) {
+        let mut heap = self.heap.lock().unwrap();
+        let mut charged = 0.0;
+        let entries: Vec<ScheduledTask> = heap
+            .drain()
+            .map(|mut task| {
+                if task.scheduler_key == scheduler_key {
+                    charged = elapsed.as_secs_f64() * task.weight;
+                    task.vruntime += charged;
+                }
+                task
+            })
+            .collect();
+        for task in entries {
+            heap.push(task);
+        }
+        drop(heap);
+
+        let mut stats = self.stats.lock().unwrap();
+        let entry = stats.entry(scheduler_key.to_string()).or_default();
+        entry.vruntime += charged;
+        entry.quanta_consumed += 1;
+    }
+
+    fn snapshot(&self) -> HashMap<String, FairnessStats> {
+        self.stats.lock().unwrap().clone()
+    }
+}
+
+/// Selectable map-phase transform for `concurrent_map_reduce`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MapTransform {
+    Identity,
+    Square,
+    Abs,
+    Scale(f64),
+}
+
+impl MapTransform {
+    fn from_options(options: &HashMap<String, String>) -> Result<Self, String> {
+        match options.get("map").map(|s| s.as_str()).unwrap_or("square") {
+            "identity" => Ok(MapTransform::Identity),
+            "square" => Ok(MapTransform::Square),
+            "abs" => Ok(MapTransform::Abs),
+            "scale" => {
+                let factor: f64 = options
+                    .get("scale_by")
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(1.0);
+                Ok(MapTransform::Scale(factor))
+            }
+            other => Err(format!("Unknown map transform: {}", other)),
+        }
+    }
+
+    fn apply(self, n: f64) -> f64 {
+        match self {
+            MapTransform::Identity => n,
+            MapTransform::Square => n * n,
+            MapTransform::Abs => n.abs(),
+            MapTransform::Scale(factor) => n * factor,
+        }
+    }
+}
+
+/// Comparison used by the `count_if` aggregate's predicate
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ComparisonOp {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+}
+
+impl ComparisonOp {
+    fn apply(self, n: f64, value: f64) -> bool {
+        match self {
+            ComparisonOp::Gt => n > value,
+            ComparisonOp::Ge => n >= value,
+            ComparisonOp::Lt => n < value,
+            ComparisonOp::Le => n <= value,
+            ComparisonOp::Eq => n == value,
+        }
+    }
+}
+
+/// Parse a predicate like `"> 5"` or `">= -3.5"` into a comparison and threshold
+fn parse_predicate(predicate: &str) -> Result<(ComparisonOp, f64), String> {
+    let predicate = predicate.trim();
+    let (op, rest) = if let Some(rest) = predicate.strip_prefix(">=") {
+        (ComparisonOp::Ge, rest)
+    } else if let Some(rest) = predicate.strip_prefix("<=") {
+        (ComparisonOp::Le, rest)
+    } else if let Some(rest) = predicate.strip_prefix("==") {
+        (ComparisonOp::Eq, rest)
+    } else if let Some(rest) = predicate.strip_prefix('>') {
+        (ComparisonOp::Gt, rest)
+    } else if let Some(rest) = predicate.strip_prefix('<') {
+        (ComparisonOp::Lt, rest)
+    } else {
+        return Err(format!("Unrecognized predicate: {}", predicate));
+    };
+
+    let value: f64 = rest
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid predicate value in: {}", predicate))?;
+    Ok((op, value))
+}
+
+/// Aggregate function selectable for `concurrent_map_reduce`
+#[derive(Debug, Clone, PartialEq)]
+enum AggregateFn {
+    Count,
+    CountIf(ComparisonOp, f64),
+    Sum,
+    Avg,
+    Min,
+    Max,
+    Median,
+    Stddev,
+    BitAnd,
+    BitOr,
+    BitXor,
+}
+
+impl AggregateFn {
+    fn from_options(options: &HashMap<String, String>) -> Result<Self, String> {
+        match options.get("operation").map(|s| s.as_str()).unwrap_or("sum") {
+            "count" => Ok(AggregateFn::Count),
+            "count_if" => {
+                let predicate = options
+                    .get("predicate")
+                    .ok_or("count_if requires a 'predicate' option, e.g. \"> 5\"")?;
+                let (op, value) = parse_predicate(predicate)?;
+                Ok(AggregateFn::CountIf(op, value))
+            }
+            "sum" => Ok(AggregateFn::Sum),
+            "avg" => Ok(AggregateFn::Avg),
+            "min" => Ok(AggregateFn::Min),
+            "max" => Ok(AggregateFn::Max),
+            "median" => Ok(AggregateFn::Median),
+            "stddev" => Ok(AggregateFn::Stddev),
+            "and" => Ok(AggregateFn::BitAnd),
+            "or" => Ok(AggregateFn::BitOr),
+            "xor" => Ok(AggregateFn::BitXor),
+            other => Err(format!("Unknown operation: {}", other)),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            AggregateFn::Count => "count",
+            AggregateFn::CountIf(..) => "count_if",
+            AggregateFn::Sum => "sum",
+            AggregateFn::Avg => "avg",
+            AggregateFn::Min => "min",
+            AggregateFn::Max => "max",
+            AggregateFn::Median => "median",
+            AggregateFn::Stddev => "stddev",
+            AggregateFn::BitAnd => "and",
+            AggregateFn::BitOr => "or",
+            AggregateFn::BitXor => "xor",
+        }
+    }
+}
+
+/// Parse every array element as an `i64`, erroring on the first non-integer value
+fn parse_as_i64(input_array: &[Value]) -> Result<Vec<i64>, String> {
+    input_array
+        .iter()
+        .map(|item| {
+            item.as_i64()
+                .or_else(|| item.as_f64().filter(|f| f.fract() == 0.0).map(|f| f as i64))
+                .ok_or_else(|| format!("Expected an integer element, found: {}", item))
+        })
+        .collect()
+}
+
+/// Progress already made on a pipeline job: where its data stands, which
+/// stage to resume from, and the stage summaries accumulated so far. Fresh
+/// runs start at stage 0 with the request's input and no results yet; resumed
+/// runs seed the data and start stage from the checkpoint being restored, but
+/// start with no stage results, since a pipeline checkpoint holds only the
+/// last stage's output.
+struct PipelineProgress {
+    pipeline_data: Value,
+    start_stage: usize,
+    stage_results: Vec<Value>,
+}
+
 /// Concurrent processor for high-performance operations
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ConcurrentProcessor {
     task_pool: Arc<Semaphore>,
     active_tasks: Arc<RwLock<HashMap<String, JoinHandle<()>>>>,
     max_concurrent_tasks: usize,
+    scheduler: FairScheduler,
 }
 
 impl ConcurrentProcessor {
     /// Create new concurrent processor
     pub async fn new() -> Result<Self, Box<dyn std::error::Error>> {
         let max_tasks = 16; // Configurable based on system
-        
+
         Ok(ConcurrentProcessor {
             task_pool: Arc::new(Semaphore::new(max_tasks)),
             active_tasks: Arc::new(RwLock::new(HashMap::new())),
             max_concurrent_tasks: max_tasks,
+            scheduler: FairScheduler::new(),
         })
     }
-    
+
     /// Process concurrent requests
     pub async fn process(
         &self,
         method: &str,
         data: &Value,
         options: &HashMap<String, String>,
+        request_id: &str,
+        memory_manager: &crate::memory::MemoryManager,
     ) -> Result<Value, String> {
         match method {
             "concurrent_parallel_process" => self.parallel_process(data).await,
-            "concurrent_batch_process" => self.batch_process(data, options).await,
+            "concurrent_batch_process" => self.batch_process(data, options, request_id, memory_manager).await,
             "concurrent_map_reduce" => self.map_reduce(data, options).await,
-            "concurrent_pipeline" => self.pipeline_process(data, options).await,
+            "concurrent_pipeline" => self.pipeline_process(data, options, request_id, memory_manager).await,
+            "concurrent_resume" => self.resume(data, options, request_id, memory_manager).await,
+            "concurrent_blacklist_checkpoint" => self.blacklist_checkpoint(request_id, memory_manager).await,
             _ => Err(format!("Unknown concurrent method: {}", method)),
         }
     }
@@ -69,23 +406,123 @@ impl ConcurrentProcessor {
             "processing_method": "parallel"
         }))
     }
-    
+
+    /// Streaming variant of [`Self::parallel_process`]
+    ///
+    /// Pushes one `ProcessingResponse` per rayon chunk down an unbounded
+    /// channel as soon as that chunk finishes, instead of collecting the
+    /// whole array before returning anything.
+    pub fn parallel_stream(
+        &self,
+        data: Value,
+        options: HashMap<String, String>,
+        request_id: String,
+    ) -> UnboundedReceiverStream<ProcessingResponse> {
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            let chunk_size: usize = options
+                .get("chunk_size")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10);
+
+            let input_array = match data.as_array() {
+                Some(arr) => arr.clone(),
+                None => {
+                    let _ = tx.send(ProcessingResponse {
+                        request_id,
+                        success: false,
+                        result: None,
+                        error: Some("Input must be an array".to_string()),
+                        metadata: None,
+                    });
+                    return;
+                }
+            };
+
+            for (chunk_index, chunk) in input_array.chunks(chunk_size.max(1)).enumerate() {
+                let results: Vec<Value> = chunk
+                    .par_iter()
+                    .map(|item| match item {
+                        Value::Number(n) => serde_json::json!(n.as_f64().unwrap_or(0.0) * 2.0),
+                        Value::String(s) => Value::String(format!("processed_{}", s)),
+                        _ => item.clone(),
+                    })
+                    .collect();
+
+                let response = ProcessingResponse {
+                    request_id: request_id.clone(),
+                    success: true,
+                    result: Some(serde_json::json!({
+                        "chunk_index": chunk_index,
+                        "results": results,
+                    })),
+                    error: None,
+                    metadata: Some(HashMap::from([
+                        ("processing_method".to_string(), "parallel_stream".to_string()),
+                    ])),
+                };
+
+                if tx.send(response).is_err() {
+                    break;
+                }
+            }
+        });
+
+        UnboundedReceiverStream::new(rx)
+    }
+
     /// Batch processing with concurrency control
-    async fn batch_process(&self, data: &Value, options: &HashMap<String, String>) -> Result<Value, String> {
+    ///
+    /// Each batch is one scheduler quantum: the request must win its turn on
+    /// the fairness heap before the batch runs, and yields back afterwards
+    /// so large jobs stay preemptible instead of monopolizing the pool.
+    async fn batch_process(
+        &self,
+        data: &Value,
+        options: &HashMap<String, String>,
+        request_id: &str,
+        memory_manager: &crate::memory::MemoryManager,
+    ) -> Result<Value, String> {
+        let input_array = data.as_array()
+            .ok_or("Input must be an array")?;
+
+        self.run_batches(input_array, options, request_id, memory_manager, 0, Vec::new())
+            .await
+    }
+
+    /// Shared batch loop used by both [`Self::batch_process`] and the `"batch"`
+    /// arm of [`Self::resume`].
+    ///
+    /// `batch_index` (and the checkpoint offset it's saved under) is always
+    /// counted from the start of `input_array`, never reset to zero for a
+    /// resumed run -- so a checkpoint's offset stays meaningful across
+    /// repeated `concurrent_resume` calls as long as `input_array` and
+    /// `batch_size` are the same ones the original run used. `batch_results`
+    /// seeds in any batches a prior run already completed.
+    async fn run_batches(
+        &self,
+        input_array: &[Value],
+        options: &HashMap<String, String>,
+        request_id: &str,
+        memory_manager: &crate::memory::MemoryManager,
+        start_batch: usize,
+        mut batch_results: Vec<Vec<Value>>,
+    ) -> Result<Value, String> {
         let batch_size: usize = options
             .get("batch_size")
             .and_then(|s| s.parse().ok())
             .unwrap_or(10);
-        
-        let input_array = data.as_array()
-            .ok_or("Input must be an array")?;
-        
-        let mut batch_results = Vec::new();
-        
-        // Process in batches
-        for batch in input_array.chunks(batch_size) {
+
+        let weight = FairScheduler::weight_from_priority(options);
+        self.scheduler.register(request_id, weight);
+
+        // Process in batches, one quantum per batch
+        for (batch_index, batch) in input_array.chunks(batch_size.max(1)).enumerate().skip(start_batch) {
+            self.scheduler.wait_for_turn(request_id, self.max_concurrent_tasks).await;
             let _permit = self.task_pool.acquire().await.map_err(|e| e.to_string())?;
-            
+
+            let quantum_start = Instant::now();
             let batch_result: Vec<Value> = batch
                 .par_iter()
                 .map(|item| {
@@ -93,14 +530,26 @@ impl ConcurrentProcessor {
                     serde_json::json!({
                         "input": item,
                         "processed": true,
-                        "batch_id": batch_results.len()
+                        "batch_id": batch_index
                     })
                 })
                 .collect();
-            
+            drop(_permit);
+
             batch_results.push(batch_result);
+
+            // Checkpoint the completed-batch index (absolute within
+            // input_array) and partial results so a panic or cancellation
+            // can resume from here via `concurrent_resume`
+            memory_manager.save_checkpoint(request_id, batch_index, serde_json::json!(batch_results));
+
+            self.scheduler
+                .account_quantum(request_id, quantum_start.elapsed().min(SCHEDULER_QUANTUM));
+            tokio::task::yield_now().await;
         }
-        
+
+        self.scheduler.deregister(request_id);
+
         Ok(serde_json::json!({
             "batches": batch_results,
             "batch_count": batch_results.len(),
@@ -109,71 +558,362 @@ impl ConcurrentProcessor {
     }
     
     /// Map-reduce processing
+    ///
+    /// The map stage applies a selectable transform (`identity`/`square`/`abs`/`scale`,
+    /// via the `map` and `scale_by` options) and the reduce stage applies a
+    /// selectable [`AggregateFn`] (`operation` option). `median` and the
+    /// bitwise `and`/`or`/`xor` operations reduce over exact `i64` values
+    /// instead of the mapped floats, erroring on non-integer input.
     async fn map_reduce(&self, data: &Value, options: &HashMap<String, String>) -> Result<Value, String> {
-        let operation = options.get("operation").unwrap_or(&"sum".to_string());
-        
+        let aggregate_fn = AggregateFn::from_options(options)?;
+        let map_transform = MapTransform::from_options(options)?;
+
         let input_array = data.as_array()
             .ok_or("Input must be an array")?;
-        
-        // Map phase - parallel processing
-        let mapped: Vec<f64> = input_array
-            .par_iter()
-            .filter_map(|item| item.as_f64())
-            .map(|n| n * n) // Square each number
-            .collect();
-        
-        // Reduce phase
-        let reduced = match operation.as_str() {
-            "sum" => mapped.iter().sum::<f64>(),
-            "avg" => mapped.iter().sum::<f64>() / mapped.len() as f64,
-            "max" => mapped.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b)),
-            "min" => mapped.iter().fold(f64::INFINITY, |a, &b| a.min(b)),
-            _ => return Err(format!("Unknown operation: {}", operation)),
+
+        if input_array.is_empty() && !matches!(aggregate_fn, AggregateFn::Count) {
+            return Err("Cannot reduce an empty input".to_string());
+        }
+
+        let result = match &aggregate_fn {
+            AggregateFn::Count => serde_json::json!(input_array.len()),
+
+            AggregateFn::CountIf(op, threshold) => {
+                let mapped = Self::map_phase(input_array, map_transform);
+                serde_json::json!(mapped.iter().filter(|&&n| op.apply(n, *threshold)).count())
+            }
+
+            AggregateFn::Sum | AggregateFn::Avg | AggregateFn::Min | AggregateFn::Max | AggregateFn::Stddev => {
+                let mapped = Self::map_phase(input_array, map_transform);
+                if mapped.is_empty() {
+                    return Err("Cannot reduce an empty input".to_string());
+                }
+
+                let value = match aggregate_fn {
+                    AggregateFn::Sum => mapped.iter().sum::<f64>(),
+                    AggregateFn::Avg => mapped.iter().sum::<f64>() / mapped.len() as f64,
+                    AggregateFn::Max => mapped.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b)),
+                    AggregateFn::Min => mapped.iter().fold(f64::INFINITY, |a, &b| a.min(b)),
+                    AggregateFn::Stddev => {
+                        let mean = mapped.iter().sum::<f64>() / mapped.len() as f64;
+                        let variance = mapped.iter().map(|n| (n - mean).powi(2)).sum::<f64>() / mapped.len() as f64;
+                        variance.sqrt()
+                    }
+                    _ => unreachable!(),
+                };
+
+                if !value.is_finite() {
+                    return Err("Aggregate result is not a finite number".to_string());
+                }
+                serde_json::json!(value)
+            }
+
+            AggregateFn::Median => {
+                let mut ints = parse_as_i64(input_array)?;
+                ints.sort_unstable();
+                let mid = ints.len() / 2;
+                let median = if ints.len() % 2 == 0 {
+                    (ints[mid - 1] + ints[mid]) as f64 / 2.0
+                } else {
+                    ints[mid] as f64
+                };
+                serde_json::json!(median)
+            }
+
+            AggregateFn::BitAnd | AggregateFn::BitOr | AggregateFn::BitXor => {
+                let ints = parse_as_i64(input_array)?;
+                let value = match aggregate_fn {
+                    AggregateFn::BitAnd => ints.iter().fold(-1i64, |a, &b| a & b),
+                    AggregateFn::BitOr => ints.iter().fold(0i64, |a, &b| a | b),
+                    AggregateFn::BitXor => ints.iter().fold(0i64, |a, &b| a ^ b),
+                    _ => unreachable!(),
+                };
+                serde_json::json!(value)
+            }
         };
-        
+
         Ok(serde_json::json!({
-            "mapped_count": mapped.len(),
-            "operation": operation,
-            "result": reduced,
-            "intermediate_results": mapped.len().min(10) // Show first 10 for debugging
+            "input_count": input_array.len(),
+            "operation": aggregate_fn.name(),
+            "result": result
         }))
     }
+
+    /// Map phase: apply `transform` to every numeric element in parallel, skipping non-numbers
+    fn map_phase(input_array: &[Value], transform: MapTransform) -> Vec<f64> {
+        input_array
+            .par_iter()
+            .filter_map(|item| item.as_f64())
+            .map(|n| transform.apply(n))
+            .collect()
+    }
     
     /// Pipeline processing
-    async fn pipeline_process(&self, data: &Value, options: &HashMap<String, String>) -> Result<Value, String> {
-        let stages: Vec<&str> = options
+    ///
+    /// Each stage is one scheduler quantum, making the pipeline preemptible
+    /// at stage boundaries just like `batch_process` is at batch boundaries.
+    async fn pipeline_process(
+        &self,
+        data: &Value,
+        options: &HashMap<String, String>,
+        request_id: &str,
+        memory_manager: &crate::memory::MemoryManager,
+    ) -> Result<Value, String> {
+        let stages: Vec<String> = options
             .get("stages")
-            .map(|s| s.split(',').collect())
-            .unwrap_or(vec!["validate", "transform", "enrich"]);
-        
-        let mut pipeline_data = data.clone();
-        let mut stage_results = Vec::new();
-        
-        for (i, stage) in stages.iter().enumerate() {
+            .map(|s| s.split(',').map(|s| s.to_string()).collect())
+            .unwrap_or_else(|| vec!["validate".to_string(), "transform".to_string(), "enrich".to_string()]);
+
+        let progress = PipelineProgress {
+            pipeline_data: data.clone(),
+            start_stage: 0,
+            stage_results: Vec::new(),
+        };
+        self.run_pipeline(&stages, progress, options, request_id, memory_manager).await
+    }
+
+    /// Shared pipeline loop used by both [`Self::pipeline_process`] and the
+    /// `"pipeline"` arm of [`Self::resume`].
+    ///
+    /// `stage_index` (and the checkpoint offset it's saved under) is always
+    /// counted from the start of `stages`, never reset to zero for a resumed
+    /// run -- so a checkpoint's offset stays meaningful across repeated
+    /// `concurrent_resume` calls as long as `stages` is the same list the
+    /// original run used. `progress.stage_results` seeds the returned
+    /// `"stages"` list; a resumed run passes it empty, so that list then
+    /// covers only the stages run by this call.
+    async fn run_pipeline(
+        &self,
+        stages: &[String],
+        progress: PipelineProgress,
+        options: &HashMap<String, String>,
+        request_id: &str,
+        memory_manager: &crate::memory::MemoryManager,
+    ) -> Result<Value, String> {
+        let PipelineProgress { mut pipeline_data, start_stage, mut stage_results } = progress;
+
+        let weight = FairScheduler::weight_from_priority(options);
+        self.scheduler.register(request_id, weight);
+
+        for (i, stage) in stages.iter().enumerate().skip(start_stage) {
+            self.scheduler.wait_for_turn(request_id, self.max_concurrent_tasks).await;
             let _permit = self.task_pool.acquire().await.map_err(|e| e.to_string())?;
-            
-            pipeline_data = match *stage {
+
+            let quantum_start = Instant::now();
+            pipeline_data = match stage.as_str() {
                 "validate" => self.validate_stage(&pipeline_data).await?,
                 "transform" => self.transform_stage(&pipeline_data).await?,
                 "enrich" => self.enrich_stage(&pipeline_data).await?,
                 "aggregate" => self.aggregate_stage(&pipeline_data).await?,
-                _ => return Err(format!("Unknown pipeline stage: {}", stage)),
+                _ => {
+                    self.scheduler.deregister(request_id);
+                    return Err(format!("Unknown pipeline stage: {}", stage));
+                }
             };
-            
+            drop(_permit);
+
             stage_results.push(serde_json::json!({
                 "stage": stage,
                 "stage_number": i + 1,
                 "output_size": pipeline_data.to_string().len()
             }));
+
+            // Checkpoint the last-finished stage index (absolute within
+            // stages) and its output so a panic or cancellation can resume
+            // from here via `concurrent_resume`
+            memory_manager.save_checkpoint(request_id, i, pipeline_data.clone());
+
+            self.scheduler
+                .account_quantum(request_id, quantum_start.elapsed().min(SCHEDULER_QUANTUM));
+            tokio::task::yield_now().await;
         }
-        
+
+        self.scheduler.deregister(request_id);
+
         Ok(serde_json::json!({
             "final_result": pipeline_data,
             "stages": stage_results,
             "pipeline_length": stages.len()
         }))
     }
-    
+
+    /// Resume a `concurrent_batch_process` or `concurrent_pipeline` job from its
+    /// latest good checkpoint
+    ///
+    /// `data` must be the *same original* input array (for `job_kind: "batch"`)
+    /// that the original run was given -- checkpoint offsets are counted from
+    /// its start, not from whatever remained after a prior resume, so that
+    /// repeated `concurrent_resume` calls stay in one consistent offset
+    /// namespace instead of re-basing against an already-truncated remainder.
+    /// `options["stages"]` (for `job_kind: "pipeline"`) must likewise be the
+    /// full original stage list.
+    ///
+    /// `options["job_kind"]` selects `"batch"` (default) or `"pipeline"`. If
+    /// the checkpoint that was resumed from turns out to be poisoned, call
+    /// `concurrent_blacklist_checkpoint` first -- that falls back to the
+    /// previous good checkpoint so the next `concurrent_resume` doesn't loop
+    /// on the same bad snapshot.
+    async fn resume(
+        &self,
+        data: &Value,
+        options: &HashMap<String, String>,
+        request_id: &str,
+        memory_manager: &crate::memory::MemoryManager,
+    ) -> Result<Value, String> {
+        let checkpoint = memory_manager
+            .latest_good_checkpoint(request_id)
+            .ok_or_else(|| format!("No usable checkpoint found for request_id '{}'", request_id))?;
+
+        let job_kind = options.get("job_kind").map(|s| s.as_str()).unwrap_or("batch");
+
+        let result = match job_kind {
+            "batch" => {
+                let input_array = data.as_array().ok_or("Input must be an array")?;
+                let prior_batch_results: Vec<Vec<Value>> = serde_json::from_value(checkpoint.partial_results.clone())
+                    .map_err(|_| "Checkpoint partial_results is not a valid batch-results array".to_string())?;
+
+                self.run_batches(input_array, options, request_id, memory_manager, checkpoint.offset + 1, prior_batch_results)
+                    .await?
+            }
+            "pipeline" => {
+                let stages: Vec<String> = options
+                    .get("stages")
+                    .map(|s| s.split(',').map(|s| s.to_string()).collect())
+                    .unwrap_or_else(|| vec!["validate".to_string(), "transform".to_string(), "enrich".to_string()]);
+
+                let progress = PipelineProgress {
+                    pipeline_data: checkpoint.partial_results.clone(),
+                    start_stage: checkpoint.offset + 1,
+                    stage_results: Vec::new(),
+                };
+                self.run_pipeline(&stages, progress, options, request_id, memory_manager).await?
+            }
+            other => return Err(format!("Unknown job_kind for concurrent_resume: {}", other)),
+        };
+
+        Ok(serde_json::json!({
+            "resumed_from_offset": checkpoint.offset,
+            "checkpoint_count": memory_manager.checkpoint_count(request_id),
+            "result": result,
+        }))
+    }
+
+    /// Blacklist the checkpoint a failed `concurrent_resume` just used, so the
+    /// next `concurrent_resume` for `request_id` falls back to the previous
+    /// good checkpoint instead of repeatedly restoring the same poisoned one
+    async fn blacklist_checkpoint(
+        &self,
+        request_id: &str,
+        memory_manager: &crate::memory::MemoryManager,
+    ) -> Result<Value, String> {
+        memory_manager.blacklist_latest_checkpoint(request_id);
+        let fallback = memory_manager.latest_good_checkpoint(request_id);
+
+        Ok(serde_json::json!({
+            "request_id": request_id,
+            "blacklisted": true,
+            "fallback_offset": fallback.map(|c| c.offset),
+        }))
+    }
+
+    /// Streaming variant of [`Self::pipeline_process`]
+    ///
+    /// Spawns the pipeline on its own task and emits an incremental
+    /// `ProcessingResponse` as soon as each stage completes, so callers can
+    /// render progress instead of waiting for the slowest stage while
+    /// holding the whole intermediate payload in memory.
+    pub fn pipeline_stream(
+        &self,
+        data: Value,
+        options: HashMap<String, String>,
+        request_id: String,
+    ) -> UnboundedReceiverStream<ProcessingResponse> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let processor = self.clone();
+
+        tokio::spawn(async move {
+            let stages: Vec<String> = options
+                .get("stages")
+                .map(|s| s.split(',').map(|s| s.to_string()).collect())
+                .unwrap_or_else(|| vec!["validate".to_string(), "transform".to_string(), "enrich".to_string()]);
+
+            let weight = FairScheduler::weight_from_priority(&options);
+            processor.scheduler.register(&request_id, weight);
+
+            let mut pipeline_data = data;
+
+            for (i, stage) in stages.iter().enumerate() {
+                processor.scheduler.wait_for_turn(&request_id, processor.max_concurrent_tasks).await;
+                let permit = match processor.task_pool.acquire().await {
+                    Ok(permit) => permit,
+                    Err(e) => {
+                        let _ = tx.send(ProcessingResponse {
+                            request_id: request_id.clone(),
+                            success: false,
+                            result: None,
+                            error: Some(e.to_string()),
+                            metadata: None,
+                        });
+                        break;
+                    }
+                };
+
+                let quantum_start = Instant::now();
+                let stage_result = match stage.as_str() {
+                    "validate" => processor.validate_stage(&pipeline_data).await,
+                    "transform" => processor.transform_stage(&pipeline_data).await,
+                    "enrich" => processor.enrich_stage(&pipeline_data).await,
+                    "aggregate" => processor.aggregate_stage(&pipeline_data).await,
+                    other => Err(format!("Unknown pipeline stage: {}", other)),
+                };
+                drop(permit);
+
+                match stage_result {
+                    Ok(output) => {
+                        pipeline_data = output.clone();
+                        processor.scheduler.account_quantum(
+                            &request_id,
+                            quantum_start.elapsed().min(SCHEDULER_QUANTUM),
+                        );
+
+                        let response = ProcessingResponse {
+                            request_id: request_id.clone(),
+                            success: true,
+                            result: Some(serde_json::json!({
+                                "stage": stage,
+                                "stage_number": i + 1,
+                                "output": output,
+                            })),
+                            error: None,
+                            metadata: Some(HashMap::from([
+                                ("stage_index".to_string(), i.to_string()),
+                            ])),
+                        };
+                        if tx.send(response).is_err() {
+                            break;
+                        }
+                    }
+                    Err(error) => {
+                        let _ = tx.send(ProcessingResponse {
+                            request_id: request_id.clone(),
+                            success: false,
+                            result: None,
+                            error: Some(error),
+                            metadata: None,
+                        });
+                        break;
+                    }
+                }
+
+                tokio::task::yield_now().await;
+            }
+
+            processor.scheduler.deregister(&request_id);
+        });
+
+        UnboundedReceiverStream::new(rx)
+    }
+
     /// Validation stage
     async fn validate_stage(&self, data: &Value) -> Result<Value, String> {
         // Simulate validation logic
@@ -215,6 +955,135 @@ impl ConcurrentProcessor {
         }))
     }
     
+    /// Privacy-preserving secure aggregation over HPKE-sealed per-report shares
+    ///
+    /// `crypto_engine` decapsulates each report into a plaintext additive
+    /// secret share; this method only ever sums/counts/means those shares in
+    /// parallel via rayon and returns the aggregate -- individual report
+    /// values are never included in the response. Batches (or, for `query_type:
+    /// "time_interval"`, each time bucket) smaller than `min_batch_size` are
+    /// rejected, since an aggregate over too few reports could let an
+    /// attacker reconstruct a single contribution.
+    ///
+    /// `query_type` selects `"fixed_size"` (default: one aggregate over the
+    /// whole batch) or `"time_interval"` (one aggregate per `time_precision`
+    /// -second bucket of each report's plaintext `"time"` field, an epoch
+    /// timestamp in seconds).
+    pub async fn secure_aggregate(
+        &self,
+        data: &Value,
+        options: &HashMap<String, String>,
+        crypto_engine: &crate::crypto::CryptoEngine,
+    ) -> Result<Value, String> {
+        let reports = data.as_array()
+            .ok_or("Input must be an array of HPKE-sealed reports")?;
+
+        let min_batch_size: usize = options
+            .get("min_batch_size")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(10);
+        let query_type = options.get("query_type").map(|s| s.as_str()).unwrap_or("fixed_size");
+
+        match query_type {
+            "fixed_size" => self.aggregate_fixed_size(reports, min_batch_size, crypto_engine),
+            "time_interval" => {
+                let time_precision: u64 = options
+                    .get("time_precision")
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(3600);
+                self.aggregate_time_interval(reports, min_batch_size, time_precision, crypto_engine)
+            }
+            other => Err(format!("Unknown query_type for concurrent_secure_aggregate: {}", other)),
+        }
+    }
+
+    /// `query_type: "fixed_size"`: one aggregate over the whole batch
+    fn aggregate_fixed_size(
+        &self,
+        reports: &[Value],
+        min_batch_size: usize,
+        crypto_engine: &crate::crypto::CryptoEngine,
+    ) -> Result<Value, String> {
+        if reports.len() < min_batch_size {
+            return Err(format!(
+                "Batch of {} reports is below the minimum batch size of {}; refusing to aggregate",
+                reports.len(),
+                min_batch_size
+            ));
+        }
+
+        let shares = crypto_engine.open_reports(reports)?;
+        Ok(serde_json::json!({
+            "query_type": "fixed_size",
+            "batch_size": shares.len(),
+            "aggregate": Self::sum_count_mean(&shares),
+        }))
+    }
+
+    /// `query_type: "time_interval"`: one aggregate per `time_precision`-second
+    /// bucket of each report's plaintext `"time"` field (an epoch timestamp
+    /// in seconds). Every report must carry a numeric `"time"`; buckets with
+    /// fewer than `min_batch_size` reports are dropped rather than returned.
+    fn aggregate_time_interval(
+        &self,
+        reports: &[Value],
+        min_batch_size: usize,
+        time_precision: u64,
+        crypto_engine: &crate::crypto::CryptoEngine,
+    ) -> Result<Value, String> {
+        if time_precision == 0 {
+            return Err("time_precision must be greater than zero".to_string());
+        }
+
+        let timestamps: Vec<u64> = reports
+            .iter()
+            .map(|report| {
+                report
+                    .get("time")
+                    .and_then(|v| v.as_u64())
+                    .ok_or("Every report needs a numeric 'time' field for query_type: \"time_interval\"")
+            })
+            .collect::<Result<_, _>>()?;
+
+        let shares = crypto_engine.open_reports(reports)?;
+
+        let mut buckets: std::collections::BTreeMap<u64, Vec<f64>> = std::collections::BTreeMap::new();
+        for (timestamp, share) in timestamps.into_iter().zip(shares) {
+            buckets.entry(timestamp / time_precision).or_default().push(share);
+        }
+
+        let dropped_buckets = buckets.values().filter(|shares| shares.len() < min_batch_size).count();
+        let intervals: Vec<Value> = buckets
+            .into_iter()
+            .filter(|(_, shares)| shares.len() >= min_batch_size)
+            .map(|(bucket, shares)| {
+                serde_json::json!({
+                    "bucket_start": bucket * time_precision,
+                    "aggregate": Self::sum_count_mean(&shares),
+                })
+            })
+            .collect();
+
+        Ok(serde_json::json!({
+            "query_type": "time_interval",
+            "time_precision": time_precision,
+            "dropped_buckets_below_min_batch_size": dropped_buckets,
+            "intervals": intervals,
+        }))
+    }
+
+    /// Sum, count, and mean of a batch of decapsulated secret shares
+    fn sum_count_mean(shares: &[f64]) -> Value {
+        let count = shares.len();
+        let sum: f64 = shares.par_iter().sum();
+        let mean = if count > 0 { sum / count as f64 } else { 0.0 };
+        serde_json::json!({
+            "sum": sum,
+            "count": count,
+            "mean": mean,
+        })
+    }
+
     /// Get concurrent processing capabilities
     pub fn get_capabilities(&self) -> Vec<String> {
         vec![
@@ -224,9 +1093,25 @@ impl ConcurrentProcessor {
             "pipeline_processing".to_string(),
             "concurrent_task_management".to_string(),
             "resource_pooling".to_string(),
+            "secure_aggregation".to_string(),
+            "parallel_streaming".to_string(),
+            "pipeline_streaming".to_string(),
         ]
     }
     
+    /// Get per-request fairness stats (vruntime, quanta consumed) from the scheduler
+    pub fn get_fairness_stats(&self) -> HashMap<String, FairnessStats> {
+        self.scheduler.snapshot()
+    }
+
+    /// Acquire a permit from the shared task pool
+    ///
+    /// Exposed so callers outside this module (e.g. the `bench_` load-generation
+    /// harness) can bound their concurrency by the same pool batch/pipeline use.
+    pub async fn acquire_task_permit(&self) -> Result<tokio::sync::SemaphorePermit<'_>, String> {
+        self.task_pool.acquire().await.map_err(|e| e.to_string())
+    }
+
     /// Get active task count
     pub async fn get_active_task_count(&self) -> usize {
         self.active_tasks.read().await.len()
@@ -236,4 +1121,411 @@ impl ConcurrentProcessor {
     pub fn get_max_concurrent_tasks(&self) -> usize {
         self.max_concurrent_tasks
     }
+}
+
+#[cfg(test)]
+mod scheduler_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn wait_for_turn_admits_up_to_max_runners_concurrently() {
+        let scheduler = FairScheduler::new();
+        scheduler.register("a", 1.0);
+        scheduler.register("b", 1.0);
+        scheduler.register("c", 1.0);
+
+        // All three have vruntime 0.0, so with max_runners=2, any two (but not
+        // all three) should be immediately admitted.
+        tokio::time::timeout(Duration::from_millis(100), scheduler.wait_for_turn("a", 2))
+            .await
+            .expect("a should be admitted immediately");
+        tokio::time::timeout(Duration::from_millis(100), scheduler.wait_for_turn("b", 2))
+            .await
+            .expect("b should be admitted immediately");
+    }
+
+    #[tokio::test]
+    async fn wait_for_turn_blocks_runners_beyond_the_limit() {
+        let scheduler = FairScheduler::new();
+        scheduler.register("a", 1.0);
+        scheduler.register("b", 1.0);
+        scheduler.account_quantum("a", Duration::from_millis(5));
+
+        // "a" has now run and accrued vruntime, so with max_runners=1 only "b"
+        // (still at vruntime 0.0) should be admitted.
+        tokio::time::timeout(Duration::from_millis(100), scheduler.wait_for_turn("b", 1))
+            .await
+            .expect("b should be admitted, it has the lowest vruntime");
+        assert!(tokio::time::timeout(Duration::from_millis(50), scheduler.wait_for_turn("a", 1))
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn account_quantum_updates_the_matching_entry_even_when_not_heap_top() {
+        let scheduler = FairScheduler::new();
+        scheduler.register("a", 1.0);
+        scheduler.register("b", 2.0);
+
+        // Charge "b" even though "a" (vruntime 0.0) currently sorts first.
+        scheduler.account_quantum("b", Duration::from_millis(10));
+
+        let stats = scheduler.snapshot();
+        assert_eq!(stats["a"].vruntime, 0.0);
+        assert!(stats["b"].vruntime > 0.0);
+        assert_eq!(stats["b"].quanta_consumed, 1);
+    }
+}
+
+#[cfg(test)]
+mod resume_tests {
+    use super::*;
+    use crate::memory::MemoryManager;
+
+    #[tokio::test]
+    async fn repeated_resume_against_the_same_original_data_keeps_a_stable_offset() {
+        let processor = ConcurrentProcessor::new().await.unwrap();
+        let memory = MemoryManager::new().unwrap();
+
+        let data = serde_json::json!((0..6).collect::<Vec<i64>>());
+        let mut options = HashMap::new();
+        options.insert("batch_size".to_string(), "1".to_string());
+
+        processor
+            .process("concurrent_batch_process", &data, &options, "job-1", &memory)
+            .await
+            .unwrap();
+        assert_eq!(memory.checkpoint_count("job-1"), 6);
+
+        // Resuming an already-finished job against the SAME original data
+        // should report the same final offset every time, not re-base
+        // against whatever a prior resume happened to see.
+        for _ in 0..2 {
+            let resumed = processor
+                .process("concurrent_resume", &data, &options, "job-1", &memory)
+                .await
+                .unwrap();
+            assert_eq!(resumed["resumed_from_offset"], 5);
+            assert_eq!(resumed["result"]["batch_count"], 6);
+        }
+    }
+
+    #[tokio::test]
+    async fn blacklisting_a_checkpoint_falls_back_to_the_previous_good_one() {
+        let processor = ConcurrentProcessor::new().await.unwrap();
+        let memory = MemoryManager::new().unwrap();
+
+        let data = serde_json::json!((0..4).collect::<Vec<i64>>());
+        let mut options = HashMap::new();
+        options.insert("batch_size".to_string(), "1".to_string());
+
+        processor
+            .process("concurrent_batch_process", &data, &options, "job-2", &memory)
+            .await
+            .unwrap();
+        assert_eq!(memory.checkpoint_count("job-2"), 4);
+
+        let blacklisted = processor
+            .process("concurrent_blacklist_checkpoint", &Value::Null, &options, "job-2", &memory)
+            .await
+            .unwrap();
+        assert_eq!(blacklisted["fallback_offset"], 2);
+
+        let resumed = processor
+            .process("concurrent_resume", &data, &options, "job-2", &memory)
+            .await
+            .unwrap();
+        assert_eq!(resumed["resumed_from_offset"], 2);
+    }
+}
+
+#[cfg(test)]
+mod map_reduce_tests {
+    use super::*;
+    use crate::memory::MemoryManager;
+
+    async fn map_reduce(data: Value, options: HashMap<String, String>) -> Result<Value, String> {
+        let processor = ConcurrentProcessor::new().await.unwrap();
+        let memory = MemoryManager::new().unwrap();
+        processor
+            .process("concurrent_map_reduce", &data, &options, "mr-test", &memory)
+            .await
+    }
+
+    #[tokio::test]
+    async fn empty_input_is_an_error_for_a_reducing_operation() {
+        let result = map_reduce(serde_json::json!([]), HashMap::new()).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn empty_input_is_fine_for_count() {
+        let mut options = HashMap::new();
+        options.insert("operation".to_string(), "count".to_string());
+        let result = map_reduce(serde_json::json!([]), options).await.unwrap();
+        assert_eq!(result["result"], 0);
+    }
+
+    #[tokio::test]
+    async fn count_if_parses_its_predicate_and_counts_matches() {
+        let mut options = HashMap::new();
+        options.insert("operation".to_string(), "count_if".to_string());
+        options.insert("map".to_string(), "identity".to_string());
+        options.insert("predicate".to_string(), ">= 3".to_string());
+        let result = map_reduce(serde_json::json!([1, 2, 3, 4, 5]), options).await.unwrap();
+        assert_eq!(result["result"], 3);
+    }
+
+    #[tokio::test]
+    async fn count_if_rejects_an_unrecognized_predicate() {
+        let mut options = HashMap::new();
+        options.insert("operation".to_string(), "count_if".to_string());
+        options.insert("predicate".to_string(), "~= 3".to_string());
+        let result = map_reduce(serde_json::json!([1, 2, 3]), options).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn median_of_an_even_length_input_averages_the_middle_pair() {
+        let mut options = HashMap::new();
+        options.insert("operation".to_string(), "median".to_string());
+        let result = map_reduce(serde_json::json!([1, 2, 3, 4]), options).await.unwrap();
+        assert_eq!(result["result"], 2.5);
+    }
+
+    #[tokio::test]
+    async fn median_rejects_non_integer_input() {
+        let mut options = HashMap::new();
+        options.insert("operation".to_string(), "median".to_string());
+        let result = map_reduce(serde_json::json!([1, 2.5, 3]), options).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn bitwise_operations_reduce_over_exact_i64_values() {
+        let mut and_options = HashMap::new();
+        and_options.insert("operation".to_string(), "and".to_string());
+        let and_result = map_reduce(serde_json::json!([6, 5]), and_options).await.unwrap();
+        assert_eq!(and_result["result"], 4);
+
+        let mut or_options = HashMap::new();
+        or_options.insert("operation".to_string(), "or".to_string());
+        let or_result = map_reduce(serde_json::json!([6, 1]), or_options).await.unwrap();
+        assert_eq!(or_result["result"], 7);
+
+        let mut xor_options = HashMap::new();
+        xor_options.insert("operation".to_string(), "xor".to_string());
+        let xor_result = map_reduce(serde_json::json!([6, 3]), xor_options).await.unwrap();
+        assert_eq!(xor_result["result"], 5);
+    }
+
+    #[tokio::test]
+    async fn bitwise_operations_reject_non_integer_input() {
+        let mut options = HashMap::new();
+        options.insert("operation".to_string(), "and".to_string());
+        let result = map_reduce(serde_json::json!([1, 2.5]), options).await;
+        assert!(result.is_err());
+    }
+}
+
+#[cfg(test)]
+mod secure_aggregate_tests {
+    use super::*;
+    use crate::crypto::CryptoEngine;
+
+    async fn sealed_report(crypto_engine: &CryptoEngine, share: f64, time: Option<u64>) -> Value {
+        let mut report = crypto_engine
+            .process("crypto_seal_report", &serde_json::json!({ "share": share }), &HashMap::new())
+            .await
+            .unwrap();
+        if let Some(time) = time {
+            report["time"] = serde_json::json!(time);
+        }
+        report
+    }
+
+    #[tokio::test]
+    async fn fixed_size_aggregates_the_whole_batch() {
+        let processor = ConcurrentProcessor::new().await.unwrap();
+        let crypto_engine = CryptoEngine::new().unwrap();
+        let mut reports = Vec::new();
+        for share in [1.0, 2.0, 3.0] {
+            reports.push(sealed_report(&crypto_engine, share, None).await);
+        }
+
+        let mut options = HashMap::new();
+        options.insert("min_batch_size".to_string(), "3".to_string());
+
+        let result = processor
+            .secure_aggregate(&serde_json::json!(reports), &options, &crypto_engine)
+            .await
+            .unwrap();
+        assert_eq!(result["aggregate"]["sum"], 6.0);
+        assert_eq!(result["aggregate"]["count"], 3);
+    }
+
+    #[tokio::test]
+    async fn time_interval_buckets_reports_by_time_precision() {
+        let processor = ConcurrentProcessor::new().await.unwrap();
+        let crypto_engine = CryptoEngine::new().unwrap();
+        let reports = vec![
+            sealed_report(&crypto_engine, 1.0, Some(0)).await,
+            sealed_report(&crypto_engine, 2.0, Some(10)).await,
+            sealed_report(&crypto_engine, 10.0, Some(3600)).await,
+            sealed_report(&crypto_engine, 20.0, Some(3601)).await,
+        ];
+
+        let mut options = HashMap::new();
+        options.insert("query_type".to_string(), "time_interval".to_string());
+        options.insert("time_precision".to_string(), "3600".to_string());
+        options.insert("min_batch_size".to_string(), "2".to_string());
+
+        let result = processor
+            .secure_aggregate(&serde_json::json!(reports), &options, &crypto_engine)
+            .await
+            .unwrap();
+        let intervals = result["intervals"].as_array().unwrap();
+        assert_eq!(intervals.len(), 2);
+        assert_eq!(intervals[0]["bucket_start"], 0);
+        assert_eq!(intervals[0]["aggregate"]["sum"], 3.0);
+        assert_eq!(intervals[1]["bucket_start"], 3600);
+        assert_eq!(intervals[1]["aggregate"]["sum"], 30.0);
+    }
+
+    #[tokio::test]
+    async fn time_interval_drops_buckets_below_the_minimum_batch_size() {
+        let processor = ConcurrentProcessor::new().await.unwrap();
+        let crypto_engine = CryptoEngine::new().unwrap();
+        let reports = vec![
+            sealed_report(&crypto_engine, 1.0, Some(0)).await,
+            sealed_report(&crypto_engine, 2.0, Some(10)).await,
+            sealed_report(&crypto_engine, 99.0, Some(3600)).await,
+        ];
+
+        let mut options = HashMap::new();
+        options.insert("query_type".to_string(), "time_interval".to_string());
+        options.insert("time_precision".to_string(), "3600".to_string());
+        options.insert("min_batch_size".to_string(), "2".to_string());
+
+        let result = processor
+            .secure_aggregate(&serde_json::json!(reports), &options, &crypto_engine)
+            .await
+            .unwrap();
+        let intervals = result["intervals"].as_array().unwrap();
+        assert_eq!(intervals.len(), 1);
+        assert_eq!(result["dropped_buckets_below_min_batch_size"], 1);
+    }
+
+    #[tokio::test]
+    async fn time_interval_requires_a_time_field_on_every_report() {
+        let processor = ConcurrentProcessor::new().await.unwrap();
+        let crypto_engine = CryptoEngine::new().unwrap();
+        let reports = vec![sealed_report(&crypto_engine, 1.0, None).await];
+
+        let mut options = HashMap::new();
+        options.insert("query_type".to_string(), "time_interval".to_string());
+
+        let result = processor
+            .secure_aggregate(&serde_json::json!(reports), &options, &crypto_engine)
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn unknown_query_type_is_rejected() {
+        let processor = ConcurrentProcessor::new().await.unwrap();
+        let crypto_engine = CryptoEngine::new().unwrap();
+        let reports = vec![sealed_report(&crypto_engine, 1.0, None).await];
+
+        let mut options = HashMap::new();
+        options.insert("query_type".to_string(), "bogus".to_string());
+
+        let result = processor
+            .secure_aggregate(&serde_json::json!(reports), &options, &crypto_engine)
+            .await;
+        assert!(result.is_err());
+    }
+}
+
+#[cfg(test)]
+mod streaming_tests {
+    use super::*;
+    use tokio_stream::StreamExt;
+
+    #[tokio::test]
+    async fn parallel_stream_emits_one_response_per_chunk_in_order() {
+        let processor = ConcurrentProcessor::new().await.unwrap();
+        let mut options = HashMap::new();
+        options.insert("chunk_size".to_string(), "2".to_string());
+
+        let mut stream = processor.parallel_stream(
+            serde_json::json!([1, 2, 3, 4, 5]),
+            options,
+            "stream-test".to_string(),
+        );
+
+        let mut chunk_indexes = Vec::new();
+        while let Some(response) = stream.next().await {
+            assert!(response.success);
+            assert_eq!(response.request_id, "stream-test");
+            chunk_indexes.push(response.result.unwrap()["chunk_index"].as_u64().unwrap());
+        }
+
+        // 5 items at chunk_size 2 is 3 chunks, delivered in order
+        assert_eq!(chunk_indexes, vec![0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn parallel_stream_reports_an_error_for_non_array_input() {
+        let processor = ConcurrentProcessor::new().await.unwrap();
+        let mut stream = processor.parallel_stream(
+            serde_json::json!("not an array"),
+            HashMap::new(),
+            "stream-test".to_string(),
+        );
+
+        let response = stream.next().await.unwrap();
+        assert!(!response.success);
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn pipeline_stream_emits_one_response_per_stage_in_order() {
+        let processor = ConcurrentProcessor::new().await.unwrap();
+        let mut options = HashMap::new();
+        options.insert("stages".to_string(), "validate,transform,enrich".to_string());
+
+        let mut stream = processor.pipeline_stream(
+            serde_json::json!({"input": 1}),
+            options,
+            "stream-test".to_string(),
+        );
+
+        let mut stage_numbers = Vec::new();
+        while let Some(response) = stream.next().await {
+            assert!(response.success);
+            stage_numbers.push(response.result.unwrap()["stage_number"].as_u64().unwrap());
+        }
+
+        assert_eq!(stage_numbers, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn pipeline_stream_reports_an_error_for_an_unknown_stage() {
+        let processor = ConcurrentProcessor::new().await.unwrap();
+        let mut options = HashMap::new();
+        options.insert("stages".to_string(), "validate,bogus".to_string());
+
+        let mut stream = processor.pipeline_stream(
+            serde_json::json!({"input": 1}),
+            options,
+            "stream-test".to_string(),
+        );
+
+        let first = stream.next().await.unwrap();
+        assert!(first.success);
+        let second = stream.next().await.unwrap();
+        assert!(!second.success);
+        assert!(stream.next().await.is_none());
+    }
 }
\ No newline at end of file
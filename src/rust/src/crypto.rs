@@ -0,0 +1,239 @@
+//! Cryptography Module for EVA Rust Core
+//!
+//! Provides cryptographic primitives, including the HPKE-based secure
+//! aggregation support used by `concurrent_secure_aggregate`
+
+use std::collections::HashMap;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use hpke::{
+    aead::AesGcm128, kdf::HkdfSha256, kem::X25519HkdfSha256, Deserializable, Kem as KemTrait,
+    OpModeR, OpModeS, Serializable,
+};
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+type HpkeKem = X25519HkdfSha256;
+type HpkeAead = AesGcm128;
+type HpkeKdf = HkdfSha256;
+
+/// Application-level HPKE `info` string, binding sealed reports to this
+/// aggregator so they can't be replayed against an unrelated HPKE context
+const HPKE_INFO: &[u8] = b"eva-secure-aggregation-v1";
+
+/// HPKE suite used by the aggregator: X25519 KEM, HKDF-SHA256, AES-128-GCM AEAD
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HpkeSuiteConfig {
+    pub kem: String,
+    pub kdf: String,
+    pub aead: String,
+}
+
+impl Default for HpkeSuiteConfig {
+    fn default() -> Self {
+        HpkeSuiteConfig {
+            kem: "X25519-HKDF-SHA256".to_string(),
+            kdf: "HKDF-SHA256".to_string(),
+            aead: "AES-128-GCM".to_string(),
+        }
+    }
+}
+
+/// Aggregator keypair and suite configuration for HPKE-sealed secure
+/// aggregation reports, following the DAP/VDAF pattern: clients encrypt an
+/// additive secret share to `public_key`; each report is decapsulated into
+/// its share, and only the aggregate of those shares is returned to callers
+/// of `concurrent_secure_aggregate`, never an individual contribution.
+pub struct AggregationConfig {
+    pub suite: HpkeSuiteConfig,
+    private_key: <HpkeKem as KemTrait>::PrivateKey,
+    public_key: <HpkeKem as KemTrait>::PublicKey,
+}
+
+impl std::fmt::Debug for AggregationConfig {
+    /// The private key never gets formatted, even in debug output
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AggregationConfig")
+            .field("suite", &self.suite)
+            .field("private_key", &"<redacted>")
+            .field("public_key", &self.public_key_b64())
+            .finish()
+    }
+}
+
+impl AggregationConfig {
+    /// Generate a fresh X25519 HPKE keypair. A production deployment would
+    /// persist the private half in a KMS instead of keeping it in memory.
+    fn new() -> Self {
+        let mut csprng = rand::rngs::StdRng::from_entropy();
+        let (private_key, public_key) = HpkeKem::gen_keypair(&mut csprng);
+        AggregationConfig {
+            suite: HpkeSuiteConfig::default(),
+            private_key,
+            public_key,
+        }
+    }
+
+    /// Base64-encoded public key, safe to hand to clients so they can seal reports
+    fn public_key_b64(&self) -> String {
+        BASE64.encode(self.public_key.to_bytes())
+    }
+
+    /// Decapsulate one HPKE-sealed report into its plaintext additive secret share
+    ///
+    /// A report is `{ "enc": <base64 ephemeral public key>, "ciphertext": <base64 sealed share> }`.
+    fn open_report(&self, report: &Value) -> Result<f64, String> {
+        let enc_b64 = report
+            .get("enc")
+            .and_then(|v| v.as_str())
+            .ok_or("Report is missing 'enc' (ephemeral public key)")?;
+        let ciphertext_b64 = report
+            .get("ciphertext")
+            .and_then(|v| v.as_str())
+            .ok_or("Report is missing 'ciphertext'")?;
+
+        let enc_bytes = BASE64
+            .decode(enc_b64)
+            .map_err(|_| "Report 'enc' is not valid base64".to_string())?;
+        let encapped_key = <HpkeKem as KemTrait>::EncappedKey::from_bytes(&enc_bytes)
+            .map_err(|_| "Report 'enc' is not a valid HPKE encapsulated key".to_string())?;
+        let ciphertext = BASE64
+            .decode(ciphertext_b64)
+            .map_err(|_| "Report 'ciphertext' is not valid base64".to_string())?;
+
+        let plaintext = hpke::single_shot_open::<HpkeAead, HpkeKdf, HpkeKem>(
+            &OpModeR::Base,
+            &self.private_key,
+            &encapped_key,
+            HPKE_INFO,
+            &ciphertext,
+            b"",
+        )
+        .map_err(|_| "Failed to decapsulate report into a secret share".to_string())?;
+
+        String::from_utf8(plaintext)
+            .map_err(|_| "Decapsulated report is not valid UTF-8".to_string())?
+            .parse::<f64>()
+            .map_err(|_| "Decapsulated report is not a number".to_string())
+    }
+
+    /// Seal a plaintext additive secret share into an HPKE report for `public_key`.
+    /// Used by tests and by callers simulating a client before real client-side
+    /// encryption exists; the aggregator itself never calls this.
+    fn seal_report(&self, share: f64) -> Result<Value, String> {
+        let mut csprng = rand::rngs::StdRng::from_entropy();
+        let plaintext = share.to_string();
+        let (encapped_key, ciphertext) = hpke::single_shot_seal::<HpkeAead, HpkeKdf, HpkeKem, _>(
+            &OpModeS::Base,
+            &self.public_key,
+            HPKE_INFO,
+            plaintext.as_bytes(),
+            b"",
+            &mut csprng,
+        )
+        .map_err(|_| "Failed to seal report".to_string())?;
+
+        Ok(serde_json::json!({
+            "enc": BASE64.encode(encapped_key.to_bytes()),
+            "ciphertext": BASE64.encode(ciphertext),
+        }))
+    }
+}
+
+/// Cryptography engine for EVA Rust Core
+#[derive(Debug)]
+pub struct CryptoEngine {
+    pub aggregation: AggregationConfig,
+}
+
+impl CryptoEngine {
+    /// Create new crypto engine
+    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(CryptoEngine {
+            aggregation: AggregationConfig::new(),
+        })
+    }
+
+    /// Process cryptography requests
+    pub async fn process(&self, method: &str, data: &Value, _options: &HashMap<String, String>) -> Result<Value, String> {
+        match method {
+            "crypto_aggregation_public_key" => Ok(serde_json::json!({
+                "suite": self.aggregation.suite,
+                "public_key": self.aggregation.public_key_b64(),
+            })),
+            "crypto_seal_report" => self.seal_report(data),
+            _ => Err(format!("Unknown crypto method: {}", method)),
+        }
+    }
+
+    /// Seal a plaintext additive secret share into an HPKE report for this
+    /// aggregator's public key. Lets a caller without its own HPKE stack
+    /// (e.g. a test harness, or a client simulating `concurrent_secure_aggregate`
+    /// traffic) produce reports that `open_reports` can decapsulate.
+    fn seal_report(&self, data: &Value) -> Result<Value, String> {
+        let share = data
+            .get("share")
+            .and_then(|v| v.as_f64())
+            .ok_or("crypto_seal_report requires a numeric 'share' in data")?;
+        self.aggregation.seal_report(share)
+    }
+
+    /// Decapsulate a batch of HPKE-sealed reports into their plaintext additive shares.
+    /// Used by `ConcurrentProcessor::secure_aggregate`, which sums these and
+    /// returns only the aggregate, never the per-report values.
+    pub fn open_reports(&self, reports: &[Value]) -> Result<Vec<f64>, String> {
+        reports
+            .iter()
+            .map(|report| self.aggregation.open_report(report))
+            .collect()
+    }
+
+    /// Get cryptography capabilities
+    pub fn get_capabilities(&self) -> Vec<String> {
+        vec![
+            "hpke_secure_aggregation".to_string(),
+            "additive_secret_sharing".to_string(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seal_then_open_recovers_the_share() {
+        let engine = CryptoEngine::new().unwrap();
+        let report = engine.aggregation.seal_report(42.5).unwrap();
+        let share = engine.aggregation.open_report(&report).unwrap();
+        assert_eq!(share, 42.5);
+    }
+
+    #[test]
+    fn tampered_ciphertext_fails_to_open() {
+        let engine = CryptoEngine::new().unwrap();
+        let mut report = engine.aggregation.seal_report(1.0).unwrap();
+        let ciphertext = report["ciphertext"].as_str().unwrap().to_string();
+        let mut bytes = BASE64.decode(ciphertext).unwrap();
+        bytes[0] ^= 0xFF;
+        report["ciphertext"] = Value::String(BASE64.encode(bytes));
+
+        assert!(engine.aggregation.open_report(&report).is_err());
+    }
+
+    #[test]
+    fn report_sealed_to_a_different_key_fails_to_open() {
+        let engine_a = CryptoEngine::new().unwrap();
+        let engine_b = CryptoEngine::new().unwrap();
+        let report = engine_a.aggregation.seal_report(7.0).unwrap();
+
+        assert!(engine_b.aggregation.open_report(&report).is_err());
+    }
+
+    #[test]
+    fn open_reports_rejects_a_malformed_report() {
+        let engine = CryptoEngine::new().unwrap();
+        let malformed = serde_json::json!({ "enc": "not-base64-!!" , "ciphertext": "also-bad"});
+        assert!(engine.open_reports(&[malformed]).is_err());
+    }
+}
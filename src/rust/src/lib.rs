@@ -7,8 +7,12 @@
 //! - Performance optimization
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use futures::future::join_all;
 use tokio::sync::RwLock;
+use tokio_stream::wrappers::UnboundedReceiverStream;
 use serde::{Deserialize, Serialize};
 use rayon::prelude::*;
 use ndarray::{Array1, Array2};
@@ -18,6 +22,154 @@ pub mod crypto;
 pub mod memory;
 pub mod optimization;
 
+/// Lower/upper bound of the latency histogram, in nanoseconds (1µs .. 60s)
+const HISTOGRAM_MIN_NANOS: u64 = 1_000;
+const HISTOGRAM_MAX_NANOS: u64 = 60_000_000_000;
+/// Number of exponential buckets spanning the histogram's range
+const HISTOGRAM_BUCKETS: usize = 64;
+
+/// Lock-light, exponentially-bucketed latency histogram (HDR-style)
+///
+/// Recording only touches atomic counters, so collecting metrics never
+/// contends with the `RwLock` guarding [`PerformanceMetrics`]. Buckets are
+/// spaced geometrically between 1µs and 60s so tail latency (p99/p999)
+/// survives instead of being averaged away.
+#[derive(Debug)]
+pub struct LatencyHistogram {
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    min_nanos: AtomicU64,
+    max_nanos: AtomicU64,
+}
+
+impl LatencyHistogram {
+    fn new() -> Self {
+        LatencyHistogram {
+            buckets: (0..HISTOGRAM_BUCKETS).map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            min_nanos: AtomicU64::new(u64::MAX),
+            max_nanos: AtomicU64::new(0),
+        }
+    }
+
+    fn bucket_span() -> f64 {
+        (HISTOGRAM_MAX_NANOS as f64 / HISTOGRAM_MIN_NANOS as f64).ln()
+    }
+
+    fn bucket_index(nanos: u64) -> usize {
+        let clamped = nanos.clamp(HISTOGRAM_MIN_NANOS, HISTOGRAM_MAX_NANOS);
+        let ratio = (clamped as f64 / HISTOGRAM_MIN_NANOS as f64).ln();
+        let idx = (ratio / Self::bucket_span() * (HISTOGRAM_BUCKETS - 1) as f64).round() as usize;
+        idx.min(HISTOGRAM_BUCKETS - 1)
+    }
+
+    /// Latency the bucket at `index` is centred on; `bucket_index` rounds to
+    /// the nearest bucket, so recorded values fall on either side of it
+    fn bucket_midpoint_nanos(index: usize) -> u64 {
+        let fraction = index as f64 / (HISTOGRAM_BUCKETS - 1) as f64;
+        (HISTOGRAM_MIN_NANOS as f64 * (fraction * Self::bucket_span()).exp()) as u64
+    }
+
+    /// Record one observed latency. Atomic-only, never blocks.
+    pub fn record(&self, duration: Duration) {
+        let nanos = duration.as_nanos().min(u64::MAX as u128) as u64;
+        self.buckets[Self::bucket_index(nanos)].fetch_add(1, AtomicOrdering::Relaxed);
+        self.count.fetch_add(1, AtomicOrdering::Relaxed);
+        self.min_nanos.fetch_min(nanos, AtomicOrdering::Relaxed);
+        self.max_nanos.fetch_max(nanos, AtomicOrdering::Relaxed);
+    }
+
+    /// Estimate the `p`th percentile latency, in seconds, from the bucket counts
+    pub fn percentile(&self, p: f64) -> f64 {
+        let total = self.count.load(AtomicOrdering::Relaxed);
+        if total == 0 {
+            return 0.0;
+        }
+
+        let target = ((p / 100.0) * total as f64).ceil() as u64;
+        let mut cumulative = 0u64;
+        for (i, bucket) in self.buckets.iter().enumerate() {
+            cumulative += bucket.load(AtomicOrdering::Relaxed);
+            if cumulative >= target {
+                return Self::bucket_midpoint_nanos(i) as f64 / 1_000_000_000.0;
+            }
+        }
+        self.max_nanos.load(AtomicOrdering::Relaxed) as f64 / 1_000_000_000.0
+    }
+
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let min = self.min_nanos.load(AtomicOrdering::Relaxed);
+        HistogramSnapshot {
+            p50: self.percentile(50.0),
+            p90: self.percentile(90.0),
+            p99: self.percentile(99.0),
+            p999: self.percentile(99.9),
+            min: if min == u64::MAX { 0.0 } else { min as f64 / 1_000_000_000.0 },
+            max: self.max_nanos.load(AtomicOrdering::Relaxed) as f64 / 1_000_000_000.0,
+            count: self.count.load(AtomicOrdering::Relaxed),
+        }
+    }
+}
+
+/// Serializable point-in-time view of a [`LatencyHistogram`], in seconds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistogramSnapshot {
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub p999: f64,
+    pub min: f64,
+    pub max: f64,
+    pub count: u64,
+}
+
+/// Global and per-subsystem latency histograms, keyed by method prefix so
+/// operators can see which subsystem owns the tail
+#[derive(Debug)]
+pub struct MetricsHistograms {
+    pub global: LatencyHistogram,
+    pub concurrent: LatencyHistogram,
+    pub crypto: LatencyHistogram,
+    pub memory: LatencyHistogram,
+    pub optimize: LatencyHistogram,
+}
+
+impl MetricsHistograms {
+    fn new() -> Self {
+        MetricsHistograms {
+            global: LatencyHistogram::new(),
+            concurrent: LatencyHistogram::new(),
+            crypto: LatencyHistogram::new(),
+            memory: LatencyHistogram::new(),
+            optimize: LatencyHistogram::new(),
+        }
+    }
+
+    /// Record `elapsed` globally and, if `method` matches a known subsystem prefix, there too
+    fn record(&self, method: &str, elapsed: Duration) {
+        self.global.record(elapsed);
+        if method.starts_with("concurrent_") {
+            self.concurrent.record(elapsed);
+        } else if method.starts_with("crypto_") {
+            self.crypto.record(elapsed);
+        } else if method.starts_with("memory_") {
+            self.memory.record(elapsed);
+        } else if method.starts_with("optimize_") {
+            self.optimize.record(elapsed);
+        }
+    }
+
+    fn snapshot(&self) -> serde_json::Value {
+        serde_json::json!({
+            "global": self.global.snapshot(),
+            "concurrent": self.concurrent.snapshot(),
+            "crypto": self.crypto.snapshot(),
+            "memory": self.memory.snapshot(),
+            "optimize": self.optimize.snapshot(),
+        })
+    }
+}
+
 /// Main EVA Rust Core structure
 #[derive(Debug)]
 pub struct EVARustCore {
@@ -26,6 +178,7 @@ pub struct EVARustCore {
     pub memory_manager: memory::MemoryManager,
     pub optimizer: optimization::Optimizer,
     pub metrics: Arc<RwLock<PerformanceMetrics>>,
+    pub histograms: Arc<MetricsHistograms>,
 }
 
 /// Performance metrics tracking
@@ -76,6 +229,7 @@ impl EVARustCore {
                 optimization_ratio: 0.0,
                 security_operations: 0,
             })),
+            histograms: Arc::new(MetricsHistograms::new()),
         };
         
         println!("✅ EVA Rust Core initialized successfully");
@@ -89,9 +243,14 @@ impl EVARustCore {
         println!("🔄 Processing Rust request: {}", request.method);
         
         let result = match request.method.as_str() {
+            // Secure aggregation bridges the crypto engine and the concurrent
+            // processor, so it's special-cased ahead of the generic "concurrent_" dispatch
+            "concurrent_secure_aggregate" => {
+                self.concurrent_processor.secure_aggregate(&request.data, &request.options, &self.crypto_engine).await
+            },
             // Concurrent processing methods
             method if method.starts_with("concurrent_") => {
-                self.concurrent_processor.process(&request.method, &request.data, &request.options).await
+                self.concurrent_processor.process(&request.method, &request.data, &request.options, &request.request_id, &self.memory_manager).await
             },
             // Cryptography methods
             method if method.starts_with("crypto_") => {
@@ -105,6 +264,10 @@ impl EVARustCore {
             method if method.starts_with("optimize_") => {
                 self.optimizer.process(&request.method, &request.data, &request.options).await
             },
+            // Load-generation / benchmarking methods
+            method if method.starts_with("bench_") => {
+                self.run_benchmark(&request.data, &request.options).await
+            },
             // General methods
             "health_check" => Ok(self.health_check().await),
             "get_capabilities" => Ok(self.get_capabilities().await),
@@ -112,7 +275,9 @@ impl EVARustCore {
             _ => Err(format!("Unknown method: {}", request.method)),
         };
         
-        let processing_time = start_time.elapsed().as_secs_f64();
+        let elapsed = start_time.elapsed();
+        let processing_time = elapsed.as_secs_f64();
+        self.histograms.record(&request.method, elapsed);
         self.update_metrics(processing_time).await;
         
         match result {
@@ -136,6 +301,33 @@ impl EVARustCore {
         }
     }
     
+    /// Stream a request that produces incremental results instead of one final value
+    ///
+    /// `process_request`'s `Result<Value, String>` can't carry a `Stream`, so
+    /// the handful of methods that emit incremental progress (currently
+    /// `concurrent_parallel_stream` and `concurrent_pipeline_stream`) get
+    /// their own entry point instead of a dispatch arm in `process_request`.
+    /// Each item on the returned stream is a `ProcessingResponse` just like
+    /// a non-streaming call would produce, one per chunk/stage.
+    pub fn process_request_stream(
+        &self,
+        request: ProcessingRequest,
+    ) -> Result<UnboundedReceiverStream<ProcessingResponse>, String> {
+        match request.method.as_str() {
+            "concurrent_parallel_stream" => Ok(self.concurrent_processor.parallel_stream(
+                request.data,
+                request.options,
+                request.request_id,
+            )),
+            "concurrent_pipeline_stream" => Ok(self.concurrent_processor.pipeline_stream(
+                request.data,
+                request.options,
+                request.request_id,
+            )),
+            other => Err(format!("Unknown streaming method: {}", other)),
+        }
+    }
+
     /// Health check
     async fn health_check(&self) -> serde_json::Value {
         serde_json::json!({
@@ -157,14 +349,179 @@ impl EVARustCore {
             "concurrent": self.concurrent_processor.get_capabilities(),
             "crypto": self.crypto_engine.get_capabilities(),
             "memory": self.memory_manager.get_capabilities(),
-            "optimization": self.optimizer.get_capabilities()
+            "optimization": self.optimizer.get_capabilities(),
+            "benchmarking": ["bench_run"],
+            "streaming": ["concurrent_parallel_stream", "concurrent_pipeline_stream"]
         })
     }
+
+    /// Drive any registered method under synthetic load, the way an HTTP load tester would
+    ///
+    /// `options` controls the load shape: `target` (required, the method to
+    /// call), `concurrency` (parallel iteration loops), `iterations` or
+    /// `bench_length_seconds` (stop condition), `ramp_up` (seconds over
+    /// which loops are staggered on start), and `delay` (pause between a
+    /// loop's calls, in ms). Loops share the concurrent processor's task
+    /// pool so benchmarking respects the same concurrency limit as real
+    /// traffic.
+    async fn run_benchmark(&self, data: &serde_json::Value, options: &HashMap<String, String>) -> Result<serde_json::Value, String> {
+        let target_method = options
+            .get("target")
+            .cloned()
+            .ok_or_else(|| "bench_ requires a 'target' option naming the method to load-test".to_string())?;
+        if target_method.starts_with("bench_") {
+            return Err("bench_ cannot target another benchmarking method".to_string());
+        }
+
+        let concurrency: usize = options.get("concurrency").and_then(|s| s.parse().ok()).unwrap_or(1).max(1);
+        let iterations: Option<u64> = options.get("iterations").and_then(|s| s.parse().ok());
+        let bench_length_seconds: Option<f64> = options.get("bench_length_seconds").and_then(|s| s.parse().ok());
+        let ramp_up_seconds: f64 = options.get("ramp_up").and_then(|s| s.parse().ok()).unwrap_or(0.0);
+        let delay_ms: u64 = options.get("delay").and_then(|s| s.parse().ok()).unwrap_or(0);
+
+        if iterations.is_none() && bench_length_seconds.is_none() {
+            return Err("bench_ requires either 'iterations' or 'bench_length_seconds'".to_string());
+        }
+        if let Some(secs) = bench_length_seconds {
+            if !secs.is_finite() || secs < 0.0 {
+                return Err("bench_length_seconds must be a finite, non-negative number".to_string());
+            }
+        }
+        if !ramp_up_seconds.is_finite() || ramp_up_seconds < 0.0 {
+            return Err("ramp_up must be a finite, non-negative number".to_string());
+        }
+
+        let mut target_options = options.clone();
+        for key in ["target", "concurrency", "iterations", "bench_length_seconds", "ramp_up", "delay"] {
+            target_options.remove(key);
+        }
+        // Split `total` across `concurrency` loops so the requested count is
+        // honored exactly: the first `total % concurrency` loops get one
+        // extra iteration instead of every loop rounding down (undershoot)
+        // or up (overshoot).
+        let iterations_per_loop = |worker_id: usize| -> Option<u64> {
+            iterations.map(|total| {
+                let base = total / concurrency as u64;
+                let remainder = total % concurrency as u64;
+                base + if (worker_id as u64) < remainder { 1 } else { 0 }
+            })
+        };
+
+        let histogram = LatencyHistogram::new();
+        let error_count = AtomicU64::new(0);
+        let achieved_concurrency = AtomicU64::new(0);
+        let bench_start = std::time::Instant::now();
+        let deadline = bench_length_seconds.map(|secs| bench_start + Duration::from_secs_f64(secs));
+
+        let worker_loops = (0..concurrency).map(|worker_id| {
+            let target_method = target_method.clone();
+            let target_data = data.clone();
+            let target_options = target_options.clone();
+            let histogram = &histogram;
+            let error_count = &error_count;
+            let achieved_concurrency = &achieved_concurrency;
+            async move {
+                if ramp_up_seconds > 0.0 && concurrency > 1 {
+                    let stagger = ramp_up_seconds / concurrency as f64 * worker_id as f64;
+                    tokio::time::sleep(Duration::from_secs_f64(stagger)).await;
+                }
+                achieved_concurrency.fetch_add(1, AtomicOrdering::Relaxed);
+                let max_iterations_for_worker = iterations_per_loop(worker_id);
+
+                let mut completed = 0u64;
+                loop {
+                    if let Some(max_iterations) = max_iterations_for_worker {
+                        if completed >= max_iterations {
+                            break;
+                        }
+                    }
+                    if let Some(dl) = deadline {
+                        if std::time::Instant::now() >= dl {
+                            break;
+                        }
+                    }
+
+                    let _permit = match self.concurrent_processor.acquire_task_permit().await {
+                        Ok(permit) => permit,
+                        Err(_) => break,
+                    };
+
+                    let request = ProcessingRequest {
+                        method: target_method.clone(),
+                        data: target_data.clone(),
+                        options: target_options.clone(),
+                        request_id: format!("bench-{}-{}", worker_id, completed),
+                    };
+
+                    let call_start = std::time::Instant::now();
+                    // Boxed: process_request dispatches back into run_benchmark,
+                    // and an async call cycle needs indirection to compile
+                    let response = Box::pin(self.process_request(request)).await;
+                    histogram.record(call_start.elapsed());
+                    if !response.success {
+                        error_count.fetch_add(1, AtomicOrdering::Relaxed);
+                    }
+
+                    completed += 1;
+
+                    if delay_ms > 0 {
+                        tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                    }
+                }
+            }
+        });
+
+        join_all(worker_loops).await;
+
+        let duration_seconds = bench_start.elapsed().as_secs_f64();
+        let latency = histogram.snapshot();
+        let throughput_ops_per_sec = if duration_seconds > 0.0 {
+            latency.count as f64 / duration_seconds
+        } else {
+            0.0
+        };
+
+        Ok(serde_json::json!({
+            "target_method": target_method,
+            "requested_concurrency": concurrency,
+            "achieved_concurrency": achieved_concurrency.load(AtomicOrdering::Relaxed),
+            "requested_iterations": iterations,
+            "achieved_iterations": latency.count,
+            "total_calls": latency.count,
+            "error_count": error_count.load(AtomicOrdering::Relaxed),
+            "duration_seconds": duration_seconds,
+            "throughput_ops_per_sec": throughput_ops_per_sec,
+            "latency": latency,
+        }))
+    }
     
     /// Get performance metrics
     async fn get_metrics(&self) -> serde_json::Value {
         let metrics = self.metrics.read().await;
-        serde_json::to_value(&*metrics).unwrap_or_default()
+        let mut value = serde_json::to_value(&*metrics).unwrap_or_default();
+
+        // Surface the fair scheduler's per-request fairness stats alongside the metrics
+        let fairness: HashMap<String, serde_json::Value> = self
+            .concurrent_processor
+            .get_fairness_stats()
+            .into_iter()
+            .map(|(request_id, stats)| {
+                (
+                    request_id,
+                    serde_json::json!({
+                        "vruntime": stats.vruntime,
+                        "quanta_consumed": stats.quanta_consumed,
+                    }),
+                )
+            })
+            .collect();
+
+        if let Some(map) = value.as_object_mut() {
+            map.insert("scheduler_fairness".to_string(), serde_json::json!(fairness));
+            map.insert("latency_histograms".to_string(), self.histograms.snapshot());
+        }
+
+        value
     }
     
     /// Update performance metrics
@@ -200,4 +557,179 @@ mod tests {
         let response = core.process_request(request).await;
         assert!(response.success);
     }
+}
+
+#[cfg(test)]
+mod histogram_tests {
+    use super::*;
+
+    #[test]
+    fn an_empty_histogram_reports_zero_everywhere() {
+        let histogram = LatencyHistogram::new();
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.count, 0);
+        assert_eq!(snapshot.p50, 0.0);
+        assert_eq!(snapshot.min, 0.0);
+        assert_eq!(snapshot.max, 0.0);
+    }
+
+    #[test]
+    fn percentiles_track_recorded_latencies_within_bucket_resolution() {
+        let histogram = LatencyHistogram::new();
+        for millis in 1..=100u64 {
+            histogram.record(Duration::from_millis(millis));
+        }
+
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.count, 100);
+        // Bucketed geometrically, so percentiles are approximate -- just
+        // check they land in the right neighborhood and are monotonic.
+        assert!(snapshot.p50 > 0.03 && snapshot.p50 < 0.07);
+        assert!(snapshot.p99 > snapshot.p50);
+        assert!(snapshot.p999 >= snapshot.p99);
+        assert!(snapshot.max >= snapshot.p999);
+    }
+
+    #[test]
+    fn min_and_max_reflect_the_smallest_and_largest_recorded_latency() {
+        let histogram = LatencyHistogram::new();
+        histogram.record(Duration::from_millis(5));
+        histogram.record(Duration::from_millis(500));
+        histogram.record(Duration::from_millis(50));
+
+        let snapshot = histogram.snapshot();
+        assert!(snapshot.min < 0.01);
+        assert!(snapshot.max > 0.4);
+    }
+
+    #[test]
+    fn bucket_index_is_monotonic_and_stays_in_range() {
+        let mut previous = LatencyHistogram::bucket_index(HISTOGRAM_MIN_NANOS);
+        for nanos in [10_000, 100_000, 1_000_000, 100_000_000, HISTOGRAM_MAX_NANOS] {
+            let index = LatencyHistogram::bucket_index(nanos);
+            assert!(index < HISTOGRAM_BUCKETS);
+            assert!(index >= previous);
+            previous = index;
+        }
+    }
+
+    #[test]
+    fn bucket_index_clamps_values_outside_the_histogram_range() {
+        assert_eq!(
+            LatencyHistogram::bucket_index(0),
+            LatencyHistogram::bucket_index(HISTOGRAM_MIN_NANOS)
+        );
+        assert_eq!(
+            LatencyHistogram::bucket_index(u64::MAX),
+            LatencyHistogram::bucket_index(HISTOGRAM_MAX_NANOS)
+        );
+    }
+
+    #[test]
+    fn metrics_histograms_route_by_method_prefix() {
+        let histograms = MetricsHistograms::new();
+        histograms.record("concurrent_batch_process", Duration::from_millis(1));
+        histograms.record("crypto_seal_report", Duration::from_millis(1));
+
+        assert_eq!(histograms.global.snapshot().count, 2);
+        assert_eq!(histograms.concurrent.snapshot().count, 1);
+        assert_eq!(histograms.crypto.snapshot().count, 1);
+        assert_eq!(histograms.memory.snapshot().count, 0);
+        assert_eq!(histograms.optimize.snapshot().count, 0);
+    }
+}
+
+#[cfg(test)]
+mod bench_tests {
+    use super::*;
+
+    fn bench_request(options: HashMap<String, String>) -> ProcessingRequest {
+        ProcessingRequest {
+            method: "bench_run".to_string(),
+            data: serde_json::Value::Null,
+            options,
+            request_id: "bench-test".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn iterations_are_distributed_exactly_across_concurrency_loops() {
+        let core = EVARustCore::new().await.unwrap();
+        let mut options = HashMap::new();
+        options.insert("target".to_string(), "health_check".to_string());
+        options.insert("concurrency".to_string(), "3".to_string());
+        options.insert("iterations".to_string(), "7".to_string());
+
+        let response = core.process_request(bench_request(options)).await;
+        assert!(response.success);
+        let result = response.result.unwrap();
+        assert_eq!(result["requested_iterations"], 7);
+        assert_eq!(result["achieved_iterations"], 7);
+        assert_eq!(result["total_calls"], 7);
+    }
+
+    #[tokio::test]
+    async fn requires_a_target() {
+        let core = EVARustCore::new().await.unwrap();
+        let mut options = HashMap::new();
+        options.insert("iterations".to_string(), "1".to_string());
+
+        let response = core.process_request(bench_request(options)).await;
+        assert!(!response.success);
+    }
+
+    #[tokio::test]
+    async fn requires_iterations_or_bench_length_seconds() {
+        let core = EVARustCore::new().await.unwrap();
+        let mut options = HashMap::new();
+        options.insert("target".to_string(), "health_check".to_string());
+
+        let response = core.process_request(bench_request(options)).await;
+        assert!(!response.success);
+    }
+
+    #[tokio::test]
+    async fn cannot_target_another_benchmark() {
+        let core = EVARustCore::new().await.unwrap();
+        let mut options = HashMap::new();
+        options.insert("target".to_string(), "bench_run".to_string());
+        options.insert("iterations".to_string(), "1".to_string());
+
+        let response = core.process_request(bench_request(options)).await;
+        assert!(!response.success);
+    }
+
+    #[tokio::test]
+    async fn rejects_a_negative_bench_length_seconds_instead_of_panicking() {
+        let core = EVARustCore::new().await.unwrap();
+        let mut options = HashMap::new();
+        options.insert("target".to_string(), "health_check".to_string());
+        options.insert("bench_length_seconds".to_string(), "-1".to_string());
+
+        let response = core.process_request(bench_request(options)).await;
+        assert!(!response.success);
+    }
+
+    #[tokio::test]
+    async fn rejects_a_nan_bench_length_seconds_instead_of_panicking() {
+        let core = EVARustCore::new().await.unwrap();
+        let mut options = HashMap::new();
+        options.insert("target".to_string(), "health_check".to_string());
+        options.insert("bench_length_seconds".to_string(), "NaN".to_string());
+
+        let response = core.process_request(bench_request(options)).await;
+        assert!(!response.success);
+    }
+
+    #[tokio::test]
+    async fn rejects_an_infinite_ramp_up_instead_of_panicking() {
+        let core = EVARustCore::new().await.unwrap();
+        let mut options = HashMap::new();
+        options.insert("target".to_string(), "health_check".to_string());
+        options.insert("iterations".to_string(), "1".to_string());
+        options.insert("ramp_up".to_string(), "inf".to_string());
+
+        let response = core.process_request(bench_request(options)).await;
+        assert!(!response.success);
+    }
 }
\ No newline at end of file
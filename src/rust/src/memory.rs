@@ -0,0 +1,172 @@
+//! Memory Management Module for EVA Rust Core
+//!
+//! Provides memory management, including a checkpoint-and-resume
+//! subsystem for long-running concurrent batch/pipeline jobs
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// One saved checkpoint for a long-running request: how far it got
+/// (completed batch index / pipeline stage index), plus the partial
+/// results produced so far
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub request_id: String,
+    pub offset: usize,
+    pub partial_results: Value,
+}
+
+impl Checkpoint {
+    /// Stable content hash, used to blacklist a snapshot that led to a crash
+    fn content_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.request_id.hash(&mut hasher);
+        self.offset.hash(&mut hasher);
+        self.partial_results.to_string().hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// Every checkpoint taken for one request, plus the hashes of any that are
+/// known to have led to a crash on resume
+#[derive(Debug, Default)]
+struct CheckpointHistory {
+    snapshots: Vec<Checkpoint>,
+    blacklisted_hashes: Vec<u64>,
+}
+
+/// Memory manager for EVA Rust Core
+#[derive(Debug)]
+pub struct MemoryManager {
+    checkpoints: Mutex<HashMap<String, CheckpointHistory>>,
+}
+
+impl MemoryManager {
+    /// Create new memory manager
+    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(MemoryManager {
+            checkpoints: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Process memory management requests
+    pub async fn process(&self, method: &str, _data: &Value, options: &HashMap<String, String>) -> Result<Value, String> {
+        match method {
+            "memory_checkpoint_status" => self.checkpoint_status(options),
+            _ => Err(format!("Unknown memory method: {}", method)),
+        }
+    }
+
+    fn checkpoint_status(&self, options: &HashMap<String, String>) -> Result<Value, String> {
+        let request_id = options
+            .get("request_id")
+            .ok_or("memory_checkpoint_status requires a 'request_id' option")?;
+
+        let checkpoints = self.checkpoints.lock().unwrap();
+        let history = checkpoints.get(request_id);
+
+        Ok(serde_json::json!({
+            "request_id": request_id,
+            "checkpoint_count": history.map(|h| h.snapshots.len()).unwrap_or(0),
+            "last_good_offset": self.latest_good_checkpoint_locked(history).map(|c| c.offset),
+        }))
+    }
+
+    fn latest_good_checkpoint_locked(&self, history: Option<&CheckpointHistory>) -> Option<Checkpoint> {
+        let history = history?;
+        history
+            .snapshots
+            .iter()
+            .rev()
+            .find(|snapshot| !history.blacklisted_hashes.contains(&snapshot.content_hash()))
+            .cloned()
+    }
+
+    /// Snapshot `partial_results` at `offset` (completed batch index / pipeline stage index)
+    pub fn save_checkpoint(&self, request_id: &str, offset: usize, partial_results: Value) {
+        let checkpoint = Checkpoint {
+            request_id: request_id.to_string(),
+            offset,
+            partial_results,
+        };
+        let mut checkpoints = self.checkpoints.lock().unwrap();
+        checkpoints
+            .entry(request_id.to_string())
+            .or_default()
+            .snapshots
+            .push(checkpoint);
+    }
+
+    /// Blacklist the checkpoint [`Self::latest_good_checkpoint`] is currently
+    /// returning for `request_id` (it led to a crash on resume), so the next
+    /// call falls back to the one before it instead of looping on a
+    /// poisoned snapshot. Blacklisting the vector's absolute tail instead
+    /// would be a no-op once that snapshot is already blacklisted -- this
+    /// walks past already-blacklisted entries so repeated calls during a
+    /// crash loop keep making progress back through history.
+    pub fn blacklist_latest_checkpoint(&self, request_id: &str) {
+        let mut checkpoints = self.checkpoints.lock().unwrap();
+        if let Some(history) = checkpoints.get_mut(request_id) {
+            let bad_hash = self
+                .latest_good_checkpoint_locked(Some(&*history))
+                .map(|checkpoint| checkpoint.content_hash());
+            if let Some(hash) = bad_hash {
+                history.blacklisted_hashes.push(hash);
+            }
+        }
+    }
+
+    /// Most recent non-blacklisted checkpoint for `request_id`, if any
+    pub fn latest_good_checkpoint(&self, request_id: &str) -> Option<Checkpoint> {
+        let checkpoints = self.checkpoints.lock().unwrap();
+        self.latest_good_checkpoint_locked(checkpoints.get(request_id))
+    }
+
+    /// Number of checkpoints saved so far for `request_id`
+    pub fn checkpoint_count(&self, request_id: &str) -> usize {
+        self.checkpoints
+            .lock()
+            .unwrap()
+            .get(request_id)
+            .map(|h| h.snapshots.len())
+            .unwrap_or(0)
+    }
+
+    /// Get memory management capabilities
+    pub fn get_capabilities(&self) -> Vec<String> {
+        vec![
+            "checkpoint_resume".to_string(),
+            "bad_state_blacklisting".to_string(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_blacklisting_walks_back_through_history_instead_of_repeating_the_tail() {
+        let manager = MemoryManager::new().unwrap();
+        for offset in 0..3 {
+            manager.save_checkpoint("job", offset, serde_json::json!(offset));
+        }
+
+        // A crash loop that keeps resuming from the same poisoned checkpoint
+        // produces no fresh snapshots, so every blacklist call must still
+        // advance past whatever is currently "good" instead of re-hashing
+        // the same already-blacklisted tail.
+        manager.blacklist_latest_checkpoint("job");
+        assert_eq!(manager.latest_good_checkpoint("job").unwrap().offset, 1);
+
+        manager.blacklist_latest_checkpoint("job");
+        assert_eq!(manager.latest_good_checkpoint("job").unwrap().offset, 0);
+
+        manager.blacklist_latest_checkpoint("job");
+        assert!(manager.latest_good_checkpoint("job").is_none());
+    }
+}